@@ -81,6 +81,12 @@ pub enum Error {
     TopicValueTooLarge,
     /// Gas
     OutOfGas,
+    /// A contract tried to call an address already on the sandbox's call
+    /// stack without setting `CallFlags::ALLOW_REENTRY`.
+    ReentrancyDenied,
+    /// `CallFlags::FORWARD_INPUT` and `CallFlags::CLONE_INPUT` were both set,
+    /// which is not a meaningful combination.
+    InvalidCallFlags,
     /// Custom Error
     Custom(&'static str),
     /// Downcast anyhow error failed
@@ -0,0 +1,17 @@
+//! Rich, RPC-facing execution results
+//!
+//! Mirrors `pallet-contracts`' `bare_call`/`bare_instantiate`: a *reverted*
+//! call is reported as a normal `ContractExecResult` with
+//! `ReturnFlags::REVERT` set in `flags`, whereas `Runtime::call` returns only
+//! the output bytes and so cannot tell a reverted call from a successful one.
+use ceres_sandbox::flag::ReturnFlags;
+use ceres_std::Vec;
+
+/// The result of a `bare_call`/`bare_instantiate` dry run.
+#[derive(Debug, Clone)]
+pub struct ContractExecResult {
+    pub gas_consumed: u64,
+    pub flags: ReturnFlags,
+    pub data: Vec<u8>,
+    pub debug_message: Vec<u8>,
+}
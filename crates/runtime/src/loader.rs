@@ -0,0 +1,123 @@
+//! Cross-contract instantiation and calls
+//!
+//! Wires `Sandbox::instantiate`/`Sandbox::seal_call` back into the
+//! orchestration that only `ceres_runtime` can perform: resolving a
+//! contract's code from the shared `Cache`, building a child `Runtime` over
+//! the same `Storage`, and running its `deploy`/`call` entrypoint.
+use crate::{Runtime, Storage};
+use ceres_executor::{Error, Result as ExecResult, ReturnCode};
+use ceres_sandbox::{
+    call::CallStack,
+    flag::{ExecReturnValue, ReturnFlags},
+    instantiate::{derive_address, ContractLoader},
+};
+use ceres_seal::RuntimeInterfaces;
+use ceres_std::Rc;
+use ceres_support::types::Cache;
+use core::cell::RefCell;
+
+/// `ContractLoader` backed by a `Cache` of known contracts and a `Storage`
+/// shared with the calling `Runtime`.
+pub struct RuntimeLoader<Ri> {
+    cache: Cache,
+    storage: Rc<RefCell<dyn Storage>>,
+    ri: Option<Ri>,
+}
+
+impl<Ri: RuntimeInterfaces + Clone> RuntimeLoader<Ri> {
+    pub fn new(cache: Cache, storage: Rc<RefCell<dyn Storage>>, ri: Option<Ri>) -> Self {
+        RuntimeLoader { cache, storage, ri }
+    }
+}
+
+impl<Ri: RuntimeInterfaces + Clone + 'static> ContractLoader for RuntimeLoader<Ri> {
+    fn instantiate(
+        &self,
+        code_hash: [u8; 32],
+        _endowment: u64,
+        gas_left: u64,
+        data: &[u8],
+        salt: &[u8],
+        call_stack: CallStack,
+        persist: bool,
+    ) -> ExecResult<([u8; 32], ExecReturnValue, u64)> {
+        let (wasm, meta) = self
+            .cache
+            .get(&code_hash)
+            .ok_or(Error::ExecuteFailed(ReturnCode::CodeNotFound))?;
+
+        // Distinct salts (and so distinct `account_id`s) must end up in
+        // distinct storage buckets even when they share the same code, so
+        // this has to be derived before the child `Runtime` is built and
+        // handed in as its `account_id`, rather than computed afterwards.
+        let account_id = derive_address(&code_hash, data, salt);
+
+        let mut child = Runtime::new(
+            &wasm,
+            meta,
+            self.storage.clone(),
+            self.cache.clone(),
+            self.ri.clone(),
+            call_stack,
+            Some(account_id),
+        )
+        .map_err(|_| Error::ExecuteFailed(ReturnCode::CodeNotFound))?;
+
+        let (ret, gas_consumed) = child
+            .raw_deploy(data.to_vec(), gas_left)
+            .map_err(|_| Error::ExecuteFailed(ReturnCode::CalleeTrapped))?;
+
+        // A reverted constructor leaves no state and no account behind.
+        if persist && !ret.flags.contains(ReturnFlags::REVERT) {
+            child
+                .flush()
+                .map_err(|_| Error::ExecuteFailed(ReturnCode::CodeNotFound))?;
+
+            self.cache.set_account(account_id, code_hash);
+        }
+
+        Ok((account_id, ret, gas_consumed))
+    }
+
+    fn call(
+        &self,
+        address: [u8; 32],
+        gas_left: u64,
+        input: ceres_std::Vec<u8>,
+        call_stack: CallStack,
+    ) -> ExecResult<(ExecReturnValue, u64)> {
+        let code_hash = self
+            .cache
+            .account_code_hash(&address)
+            .ok_or(Error::ExecuteFailed(ReturnCode::NotCallable))?;
+
+        let (wasm, meta) = self
+            .cache
+            .get(&code_hash)
+            .ok_or(Error::ExecuteFailed(ReturnCode::NotCallable))?;
+
+        let mut child = Runtime::new(
+            &wasm,
+            meta,
+            self.storage.clone(),
+            self.cache.clone(),
+            self.ri.clone(),
+            call_stack,
+            Some(address),
+        )
+        .map_err(|_| Error::ExecuteFailed(ReturnCode::NotCallable))?;
+
+        let (ret, gas_consumed) = child
+            .raw_call(input, gas_left)
+            .map_err(|_| Error::ExecuteFailed(ReturnCode::CalleeTrapped))?;
+
+        // A reverted call leaves the callee's stored state as it was.
+        if !ret.flags.contains(ReturnFlags::REVERT) {
+            child
+                .flush()
+                .map_err(|_| Error::ExecuteFailed(ReturnCode::NotCallable))?;
+        }
+
+        Ok((ret, gas_consumed))
+    }
+}
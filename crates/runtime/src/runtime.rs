@@ -1,9 +1,12 @@
 //! Ceres Runtime
-use crate::{storage::MemoryStorage, util, Error, Metadata, Result, Storage};
+use crate::{
+    loader::RuntimeLoader, storage::MemoryStorage, util, Error, Metadata, Result, Storage,
+};
 use ceres_executor::{Builder, Instance, Memory};
 use ceres_sandbox::{Sandbox, Transaction};
 use ceres_seal::RuntimeInterfaces;
 use ceres_std::{Rc, String, ToString, Vec};
+use ceres_support::types::Cache;
 use core::cell::RefCell;
 use parity_wasm::elements::Module;
 
@@ -13,11 +16,33 @@ pub struct Runtime {
     instance: Instance<Sandbox>,
     pub metadata: Metadata,
     storage: Rc<RefCell<dyn Storage>>,
+    /// The key this instance's state is stored under - its account id when
+    /// it was instantiated as a child contract (so distinct salts of the
+    /// same code don't share state), or its own code hash for a top-level
+    /// `Runtime` with no account id of its own.
+    storage_key: [u8; 32],
+    cache: Cache,
+    gas_consumed: u64,
+}
+
+/// Concatenate `selector` with each already-SCALE-encoded argument in
+/// `args`, producing the raw input buffer a constructor/message entrypoint
+/// expects.
+fn encode_input(selector: &[u8], args: &[Vec<u8>]) -> Vec<u8> {
+    let mut input = selector.to_vec();
+    for arg in args {
+        input.extend_from_slice(arg);
+    }
+    input
 }
 
 impl Runtime {
     /// Create runtime from contract
-    pub fn from_contract(contract: &[u8], ri: Option<impl RuntimeInterfaces>) -> Result<Runtime> {
+    pub fn from_contract(
+        contract: &[u8],
+        cache: Cache,
+        ri: Option<impl RuntimeInterfaces + Clone + 'static>,
+    ) -> Result<Runtime> {
         let meta = serde_json::from_str::<Metadata>(&String::from_utf8_lossy(contract))
             .map_err(|_| Error::DecodeContractFailed)?;
 
@@ -26,7 +51,10 @@ impl Runtime {
                 .map_err(|_| Error::DecodeContractFailed)?,
             meta,
             Rc::new(RefCell::new(MemoryStorage::new())),
+            cache,
             ri,
+            Rc::new(RefCell::new(Vec::new())),
+            None,
         )
     }
 
@@ -34,7 +62,8 @@ impl Runtime {
     pub fn from_contract_and_storage(
         contract: &[u8],
         storage: Rc<RefCell<impl Storage + 'static>>,
-        ri: Option<impl RuntimeInterfaces>,
+        cache: Cache,
+        ri: Option<impl RuntimeInterfaces + Clone + 'static>,
     ) -> Result<Runtime> {
         let meta = serde_json::from_str::<Metadata>(&String::from_utf8_lossy(contract))
             .map_err(|_| Error::DecodeContractFailed)?;
@@ -44,7 +73,10 @@ impl Runtime {
                 .map_err(|_| Error::DecodeContractFailed)?,
             meta,
             storage,
+            cache,
             ri,
+            Rc::new(RefCell::new(Vec::new())),
+            None,
         )
     }
 
@@ -52,23 +84,43 @@ impl Runtime {
     pub fn from_metadata_and_storage(
         meta: Metadata,
         storage: Rc<RefCell<impl Storage + 'static>>,
-        ri: Option<impl RuntimeInterfaces>,
+        cache: Cache,
+        ri: Option<impl RuntimeInterfaces + Clone + 'static>,
     ) -> Result<Runtime> {
         Self::new(
             &hex::decode(&meta.source.wasm.as_bytes()[2..])
                 .map_err(|_| Error::DecodeContractFailed)?,
             meta,
             storage,
+            cache,
             ri,
+            Rc::new(RefCell::new(Vec::new())),
+            None,
         )
     }
 
     /// New runtime
+    ///
+    /// `call_stack` is the reentrancy guard shared across every `Runtime` on
+    /// the same call chain. Top-level entry points above start a fresh,
+    /// empty one; `RuntimeLoader` passes its own `call_stack` back in when
+    /// recursing into a child contract, so the guard still sees the whole
+    /// chain rather than resetting at each hop.
+    ///
+    /// `account_id` is the key this instance's state is stored under once
+    /// instantiated - distinct contract instances sharing the same code but
+    /// different `account_id`s (e.g. distinct instantiation salts) must not
+    /// be keyed by `code_hash` alone, or they'd silently share one storage
+    /// bucket. `None` falls back to `code_hash`, for a top-level `Runtime`
+    /// with no account id of its own.
     pub fn new(
         b: &[u8],
         metadata: Metadata,
         storage: Rc<RefCell<impl Storage + 'static>>,
-        ri: Option<impl RuntimeInterfaces>,
+        cache: Cache,
+        ri: Option<impl RuntimeInterfaces + Clone + 'static>,
+        call_stack: ceres_sandbox::call::CallStack,
+        account_id: Option<[u8; 32]>,
     ) -> Result<Runtime> {
         let mut el = Module::from_bytes(b).map_err(|_| Error::ParseWasmModuleFailed)?;
         if el.has_names_section() {
@@ -82,17 +134,30 @@ impl Runtime {
         let limit = util::scan_imports(&el).map_err(|_| Error::CalcuateMemoryLimitFailed)?;
         let mem = Memory::new(limit.0, limit.1).map_err(|_| Error::AllocMemoryFailed)?;
 
+        let code_hash = util::parse_code_hash(&metadata.source.hash)?;
+        let storage_key = account_id.unwrap_or(code_hash);
+
         // Get storage
         let storage_mut = storage.borrow_mut();
-        let state =
-            if let Some(state) = storage_mut.get(util::parse_code_hash(&metadata.source.hash)?) {
-                state
-            } else {
-                storage_mut.new_state()
-            };
+        let state = if let Some(state) = storage_mut.get(storage_key) {
+            state
+        } else {
+            storage_mut.new_state()
+        };
+
+        // Remember this contract's code so that other runtimes sharing the
+        // same `Cache` can resolve it by `code_hash` when instantiating it as
+        // a child contract.
+        cache.insert(code_hash, b.to_vec(), metadata.clone());
 
         // Create Sandbox and Builder
-        let sandbox = Rc::new(RefCell::new(Sandbox::new(mem, state)));
+        let loader = RuntimeLoader::new(cache.clone(), storage.clone(), ri.clone());
+        let sandbox = Rc::new(RefCell::new(Sandbox::new(
+            mem,
+            state,
+            Rc::new(loader),
+            call_stack,
+        )));
 
         // Construct interfaces
         let mut builder = Builder::new().add_host_parcels(ceres_seal::pallet_contracts(ri));
@@ -117,57 +182,105 @@ impl Runtime {
             instance,
             metadata,
             storage,
+            storage_key,
+            cache,
+            gas_consumed: 0,
         })
     }
 
+    /// Gas consumed by the most recent `deploy`/`call`.
+    pub fn gas_consumed(&self) -> u64 {
+        self.gas_consumed
+    }
+
+    /// Events emitted by the contract during the most recent `deploy`/`call`.
+    pub fn events(&self) -> Vec<ceres_sandbox::event::ContractEvent> {
+        self.sandbox.borrow().ext.events.clone()
+    }
+
+    /// Enable or disable `seal_debug_message` recording, mirroring the
+    /// `CONTRACTS_DEBUG_OUTPUT` toggle real `pallet-contracts` nodes expose.
+    pub fn enable_debug_message(&mut self, enabled: bool) {
+        self.sandbox.borrow_mut().debug = enabled;
+    }
+
+    /// Take the debug message accumulated by `ink::env::debug_println!` calls
+    /// during the most recent `deploy`/`call`, clearing the buffer.
+    pub fn take_debug_message(&mut self) -> Option<String> {
+        self.sandbox.borrow_mut().debug_message.take()
+    }
+
     /// Deploy contract
-    pub fn deploy(&mut self, method: &str, args: &[&str], tx: Option<Transaction>) -> Result<()> {
+    ///
+    /// `args` are the constructor's arguments, each already SCALE-encoded by
+    /// the caller - `deploy` only prepends the constructor's selector, it
+    /// doesn't parse or type-check them.
+    pub fn deploy(
+        &mut self,
+        method: &str,
+        args: Vec<Vec<u8>>,
+        tx: Option<Transaction>,
+        gas_limit: Option<u64>,
+    ) -> Result<()> {
         if let Some(tx) = tx {
             self.sandbox.borrow_mut().tx = tx;
         }
+        self.sandbox.borrow_mut().ext.events.clear();
+        self.sandbox.borrow_mut().debug_message = None;
 
         let constructors = self.metadata.constructors();
-        let (selector, tys) = constructors.get(method).ok_or(Error::GetMethodFailed {
+        let (selector, _) = constructors.get(method).ok_or(Error::GetMethodFailed {
             name: method.to_string(),
         })?;
 
         let mut bm = self.sandbox.borrow_mut();
-        bm.input = Some(util::parse_args(
-            selector,
-            args,
-            tys.iter().map(|ty| ty.1).collect(),
-        )?);
+        bm.input = Some(encode_input(selector, &args));
+        bm.gas_meter = ceres_sandbox::contract::GasMeter::new(
+            gas_limit.unwrap_or(ceres_sandbox::contract::DEFAULT_GAS_LIMIT),
+        );
+        let gas_before = bm.gas_meter.gas_left;
+
         self.instance
             .invoke("deploy", &[], &mut bm)
             .map_err(|error| Error::DeployContractFailed { error })?;
 
+        self.gas_consumed = gas_before.saturating_sub(bm.gas_meter.gas_left);
+
         Ok(())
     }
 
     /// Call contract
+    ///
+    /// `args` are the message's arguments, each already SCALE-encoded by the
+    /// caller - see `deploy` above.
     pub fn call(
         &mut self,
         method: &str,
-        args: &[&str],
+        args: Vec<Vec<u8>>,
         tx: Option<Transaction>,
+        gas_limit: Option<u64>,
     ) -> Result<Vec<u8>> {
         if let Some(tx) = tx {
             self.sandbox.borrow_mut().tx = tx;
         }
+        self.sandbox.borrow_mut().ext.events.clear();
+        self.sandbox.borrow_mut().debug_message = None;
 
         let messages = self.metadata.messages();
-        let (selector, tys) = messages.get(method).ok_or(Error::GetMethodFailed {
+        let (selector, _) = messages.get(method).ok_or(Error::GetMethodFailed {
             name: method.to_string(),
         })?;
 
         let mut bm = self.sandbox.borrow_mut();
-        bm.input = Some(util::parse_args(
-            selector,
-            args,
-            tys.iter().map(|ty| ty.1).collect(),
-        )?);
+        bm.input = Some(encode_input(selector, &args));
+        bm.gas_meter = ceres_sandbox::contract::GasMeter::new(
+            gas_limit.unwrap_or(ceres_sandbox::contract::DEFAULT_GAS_LIMIT),
+        );
+        let gas_before = bm.gas_meter.gas_left;
 
         let res = self.instance.invoke("call", &[], &mut bm);
+        self.gas_consumed = gas_before.saturating_sub(bm.gas_meter.gas_left);
+
         if let Some(ret) = bm.ret.take() {
             return Ok(ret);
         } else {
@@ -177,12 +290,185 @@ impl Runtime {
         Ok(vec![])
     }
 
+    /// RPC-style dry run of `call`: a reverted message is reported as a
+    /// `ContractExecResult` with `ReturnFlags::REVERT` set (`call` returns
+    /// only the output bytes, without the flag), and gas consumption plus
+    /// any `ink::env::debug_println!` output are surfaced alongside it.
+    pub fn bare_call(
+        &mut self,
+        method: &str,
+        args: Vec<Vec<u8>>,
+        tx: Option<Transaction>,
+        gas_limit: u64,
+    ) -> Result<crate::bare::ContractExecResult> {
+        if let Some(tx) = tx {
+            self.sandbox.borrow_mut().tx = tx;
+        }
+        self.sandbox.borrow_mut().ext.events.clear();
+        let was_debug = self.sandbox.borrow().debug;
+        self.enable_debug_message(true);
+
+        let outcome = (|| {
+            let messages = self.metadata.messages();
+            let (selector, _) = messages.get(method).ok_or(Error::GetMethodFailed {
+                name: method.to_string(),
+            })?;
+            let input = encode_input(selector, &args);
+            self.invoke_entrypoint("call", input, gas_limit)
+                .map_err(|error| Error::CallContractFailed { error })
+        })();
+
+        let debug_message = self.take_debug_message().unwrap_or_default().into_bytes();
+        self.enable_debug_message(was_debug);
+
+        let (ret, gas_consumed) = outcome?;
+        self.gas_consumed = gas_consumed;
+
+        Ok(crate::bare::ContractExecResult {
+            gas_consumed,
+            flags: ret.flags,
+            data: ret.data,
+            debug_message,
+        })
+    }
+
+    /// RPC-style dry run of `deploy`: returns the account id the contract
+    /// would be instantiated at together with a `ContractExecResult`, so a
+    /// reverted constructor is reported rather than surfacing as
+    /// `Error::DeployContractFailed`.
+    ///
+    /// Runs the constructor against an isolated child instance - the same
+    /// child-instance construction `RuntimeLoader::instantiate` uses for a
+    /// real cross-contract instantiate - rather than against `self`'s own
+    /// live storage, so a dry run can't corrupt an already-deployed instance.
+    /// The child is discarded instead of flushed, so unlike `bare_call` this
+    /// can't surface `self`'s own `ink::env::debug_println!` output.
+    ///
+    /// `tx` is accepted for symmetry with `deploy`/`call`/`bare_call`, but
+    /// like every other cross-contract hop the constructor runs with no
+    /// `Transaction` context of its own - setting it on `self`'s sandbox
+    /// would do nothing but leak it into `self`'s later `call`s, since the
+    /// constructor never runs against `self`'s own sandbox.
+    pub fn bare_instantiate(
+        &mut self,
+        method: &str,
+        args: Vec<Vec<u8>>,
+        salt: &[u8],
+        _tx: Option<Transaction>,
+        gas_limit: u64,
+    ) -> Result<([u8; 32], crate::bare::ContractExecResult)> {
+        let constructors = self.metadata.constructors();
+        let (selector, _) = constructors.get(method).ok_or(Error::GetMethodFailed {
+            name: method.to_string(),
+        })?;
+        let input = encode_input(selector, &args);
+
+        let code_hash = util::parse_code_hash(&self.metadata.source.hash)?;
+
+        let sandbox = self.sandbox.borrow();
+        let loader = sandbox.loader.clone();
+        let call_stack = sandbox.call_stack.clone();
+        drop(sandbox);
+
+        let (account_id, ret, gas_consumed) = loader
+            .instantiate(code_hash, 3, gas_limit, &input, salt, call_stack, false)
+            .map_err(|error| Error::DeployContractFailed { error })?;
+
+        self.gas_consumed = gas_consumed;
+
+        Ok((
+            account_id,
+            crate::bare::ContractExecResult {
+                gas_consumed,
+                flags: ret.flags,
+                data: ret.data,
+                debug_message: Vec::new(),
+            },
+        ))
+    }
+
+    /// Run `deploy` against an already SCALE-encoded constructor call
+    /// (selector ++ arguments), as used when instantiating a contract as a
+    /// child of another running contract, where the caller has already
+    /// assembled the selector together with the arguments.
+    ///
+    /// Returns the constructor's `ExecReturnValue` together with the gas it
+    /// consumed, given `gas_left` to start from.
+    pub fn raw_deploy(
+        &mut self,
+        input: Vec<u8>,
+        gas_left: u64,
+    ) -> Result<(ceres_sandbox::flag::ExecReturnValue, u64)> {
+        self.invoke_entrypoint("deploy", input, gas_left)
+            .map_err(|error| Error::DeployContractFailed { error })
+    }
+
+    /// Run `call` against an already SCALE-encoded message call (selector ++
+    /// arguments), as used when calling a contract as a child of another
+    /// running contract.
+    ///
+    /// Returns the message's `ExecReturnValue` together with the gas it
+    /// consumed, given `gas_left` to start from.
+    pub fn raw_call(
+        &mut self,
+        input: Vec<u8>,
+        gas_left: u64,
+    ) -> Result<(ceres_sandbox::flag::ExecReturnValue, u64)> {
+        self.invoke_entrypoint("call", input, gas_left)
+            .map_err(|error| Error::CallContractFailed { error })
+    }
+
+    fn invoke_entrypoint(
+        &mut self,
+        entrypoint: &str,
+        input: Vec<u8>,
+        gas_left: u64,
+    ) -> core::result::Result<(ceres_sandbox::flag::ExecReturnValue, u64), ceres_executor::Error>
+    {
+        use ceres_sandbox::flag::ReturnFlags;
+
+        let mut bm = self.sandbox.borrow_mut();
+        bm.input = Some(input);
+        bm.gas_meter = ceres_sandbox::contract::GasMeter::new(gas_left);
+
+        let res = self.instance.invoke(entrypoint, &[], &mut bm);
+        let gas_consumed = gas_left.saturating_sub(bm.gas_meter.gas_left);
+        let ret = bm.ret.take();
+        let ret_flags = bm.ret_flags;
+        drop(bm);
+
+        // A contract exits either by trapping the host to unwind with the
+        // output it staged via `seal_return`/`CallFlags::TAIL_CALL` (`bm.ret`
+        // is set whether or not that trap surfaces here as `Err`), or by
+        // returning normally with no explicit output. Only a trap that
+        // *isn't* one of these staged returns is a genuine failure - relying
+        // on `data.is_empty()` to guess REVERT mis-classified a revert with
+        // no output buffer as success.
+        match ret {
+            Some(data) => Ok((
+                ceres_sandbox::flag::ExecReturnValue {
+                    flags: ret_flags,
+                    data,
+                },
+                gas_consumed,
+            )),
+            None => res.map(|_| {
+                (
+                    ceres_sandbox::flag::ExecReturnValue {
+                        flags: ReturnFlags::empty(),
+                        data: Vec::new(),
+                    },
+                    gas_consumed,
+                )
+            }),
+        }
+    }
+
     /// Flush storage
     pub fn flush(&mut self) -> Result<()> {
-        self.storage.borrow_mut().set(
-            util::parse_code_hash(&self.metadata.source.hash)?,
-            self.sandbox.borrow().state.clone(),
-        )?;
+        self.storage
+            .borrow_mut()
+            .set(self.storage_key, self.sandbox.borrow().state.clone())?;
 
         Ok(())
     }
@@ -0,0 +1,99 @@
+//! `seal_call` CallFlags
+use crate::{contract::GasMeter, flag::ExecReturnValue, Sandbox};
+use bitflags::bitflags;
+use ceres_executor::{Error, Result};
+use ceres_std::{Rc, Vec};
+use core::cell::RefCell;
+
+/// The addresses of the callees entered through `seal_call` that are still
+/// executing on this call chain, innermost last.
+///
+/// `seal_call` pushes the callee's address before the call and pops it
+/// afterwards. The outermost contract of a top-level `deploy`/`call`, and a
+/// constructor run through `instantiate`, are not pushed, so a call back
+/// into one of those is not rejected by this guard.
+///
+/// Shared (via `Rc<RefCell<_>>`) by every `Sandbox` instantiated while
+/// handling one top-level `deploy`/`call`, including the fresh `Sandbox`
+/// each cross-contract hop builds in `ceres_runtime::RuntimeLoader` - a
+/// plain `Vec` field would only ever see the current contract's own frame
+/// and could never catch an `A -> B -> A` cycle between callees.
+pub type CallStack = Rc<RefCell<Vec<[u8; 32]>>>;
+
+bitflags! {
+    /// Flags used by a contract to customize exactly how the call to another
+    /// contract is processed.
+    ///
+    /// Mirrors `pallet_contracts`' `CallFlags`.
+    #[derive(Default)]
+    pub struct CallFlags: u32 {
+        /// Forward the input of the current call to the callee, consuming
+        /// it: the caller's own input is no longer available once the call
+        /// returns.
+        const FORWARD_INPUT = 0b0000_0001;
+        /// Identical to `FORWARD_INPUT`, except the caller's input is kept
+        /// around so it may still be read after the call returns.
+        const CLONE_INPUT = 0b0000_0010;
+        /// Don't return to the caller after the call: the callee's
+        /// `ExecReturnValue` directly becomes the current frame's return
+        /// value, terminating the caller.
+        const TAIL_CALL = 0b0000_0100;
+        /// Allow the callee to reenter the caller's execution context. If
+        /// unset, calling back into an address that is already on the call
+        /// stack traps.
+        const ALLOW_REENTRY = 0b0000_1000;
+    }
+}
+
+impl Sandbox {
+    /// Cross-contract call, honouring ink!'s `CallFlags` semantics.
+    ///
+    /// `input_data` is used verbatim unless `FORWARD_INPUT` or `CLONE_INPUT`
+    /// is set, in which case the caller's own `self.input` is reused instead.
+    pub fn seal_call(
+        &mut self,
+        address: [u8; 32],
+        flags: CallFlags,
+        gas_meter: &mut GasMeter,
+        input_data: Vec<u8>,
+    ) -> Result<ExecReturnValue> {
+        gas_meter.charge(crate::gas::CallToken)?;
+
+        if flags.contains(CallFlags::FORWARD_INPUT) && flags.contains(CallFlags::CLONE_INPUT) {
+            return Err(Error::InvalidCallFlags);
+        }
+
+        if !flags.contains(CallFlags::ALLOW_REENTRY) && self.call_stack.borrow().contains(&address)
+        {
+            return Err(Error::ReentrancyDenied);
+        }
+
+        let input = if flags.contains(CallFlags::FORWARD_INPUT) {
+            self.input.take().unwrap_or_default()
+        } else if flags.contains(CallFlags::CLONE_INPUT) {
+            self.input.clone().unwrap_or_default()
+        } else {
+            input_data
+        };
+
+        self.call_stack.borrow_mut().push(address);
+        let result = self
+            .loader
+            .call(address, gas_meter.gas_left, input, self.call_stack.clone());
+        self.call_stack.borrow_mut().pop();
+
+        let (ret, gas_consumed) = result?;
+        gas_meter.gas_left = gas_meter.gas_left.saturating_sub(gas_consumed);
+
+        if flags.contains(CallFlags::TAIL_CALL) {
+            self.ret_flags = ret.flags;
+            self.ret = Some(ret.data.clone());
+            return Err(Error::ReturnData {
+                flags: ret.flags.bits(),
+                data: ret.data,
+            });
+        }
+
+        Ok(ret)
+    }
+}
@@ -0,0 +1,32 @@
+//! Gas-metered execution context
+use crate::gas::Schedule;
+
+/// Default gas limit used when a caller doesn't specify one explicitly.
+pub const DEFAULT_GAS_LIMIT: u64 = 10_000_000_000;
+
+/// Tracks the gas budget remaining for the current execution.
+///
+/// Threaded by value through the call/instantiate entrypoints rather than
+/// read off `Sandbox` directly, since those entrypoints also need a mutable
+/// borrow of other `Sandbox` fields at the same time.
+#[derive(Debug, Clone)]
+pub struct GasMeter {
+    pub gas_left: u64,
+    pub schedule: Schedule,
+}
+
+impl GasMeter {
+    /// Start a fresh meter with `gas_limit` gas and the default `Schedule`.
+    pub fn new(gas_limit: u64) -> Self {
+        GasMeter {
+            gas_left: gas_limit,
+            schedule: Schedule::default(),
+        }
+    }
+}
+
+impl Default for GasMeter {
+    fn default() -> Self {
+        GasMeter::new(DEFAULT_GAS_LIMIT)
+    }
+}
@@ -0,0 +1,25 @@
+//! Debug message buffer
+use crate::Sandbox;
+use ceres_executor::{Error, Result, ReturnCode};
+use ceres_std::{String, Vec};
+
+impl Sandbox {
+    /// `seal_debug_message`: append `message` (expected to be UTF-8) to the
+    /// debug buffer when debug recording is enabled.
+    ///
+    /// Returns `ReturnCode::Success` and records the message when
+    /// `self.debug` is set; otherwise leaves the buffer untouched and
+    /// returns `ReturnCode::LoggingDisabled`.
+    pub fn seal_debug_message(&mut self, message: Vec<u8>) -> Result<ReturnCode> {
+        if !self.debug {
+            return Ok(ReturnCode::LoggingDisabled);
+        }
+
+        let message = String::from_utf8(message).map_err(|_| Error::DecodeRuntimeValueFailed)?;
+        self.debug_message
+            .get_or_insert_with(String::new)
+            .push_str(&message);
+
+        Ok(ReturnCode::Success)
+    }
+}
@@ -0,0 +1,64 @@
+//! Contract events
+use crate::{contract::GasMeter, gas::DepositEventToken, Sandbox};
+use ceres_executor::{Error, Result};
+use ceres_std::Vec;
+use parity_scale_codec::Decode;
+
+/// Maximum number of topics a single deposited event may carry.
+pub const MAX_TOPICS: usize = 4;
+
+/// An event emitted by a contract via `seal_deposit_event`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ContractEvent {
+    pub topics: Vec<[u8; 32]>,
+    pub data: Vec<u8>,
+}
+
+impl Sandbox {
+    /// `seal_deposit_event`: decode the SCALE-encoded `topics`, validate
+    /// them, and append the event to `self.ext.events`.
+    ///
+    /// Rejects more than [`MAX_TOPICS`] topics with `Error::TooManyTopics`,
+    /// a repeated topic with `Error::DuplicateTopics`, and any topic whose
+    /// length isn't exactly 32 bytes with `Error::TopicValueTooLarge`.
+    pub fn seal_deposit_event(
+        &mut self,
+        gas_meter: &mut GasMeter,
+        topics: Vec<u8>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let topics = <Vec<Vec<u8>>>::decode(&mut &topics[..])
+            .map_err(|_| Error::DecodeRuntimeValueFailed)?;
+
+        if topics.len() > MAX_TOPICS {
+            return Err(Error::TooManyTopics);
+        }
+
+        let mut fixed_topics = Vec::with_capacity(topics.len());
+        for topic in topics {
+            if topic.len() != 32 {
+                return Err(Error::TopicValueTooLarge);
+            }
+
+            let mut fixed = [0u8; 32];
+            fixed.copy_from_slice(&topic);
+
+            if fixed_topics.contains(&fixed) {
+                return Err(Error::DuplicateTopics);
+            }
+            fixed_topics.push(fixed);
+        }
+
+        gas_meter.charge(DepositEventToken {
+            topics: fixed_topics.len() as u64,
+            data_len: data.len() as u64,
+        })?;
+
+        self.ext.events.push(ContractEvent {
+            topics: fixed_topics,
+            data,
+        });
+
+        Ok(())
+    }
+}
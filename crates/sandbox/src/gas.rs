@@ -0,0 +1,122 @@
+//! Gas metering
+//!
+//! Modeled on `pallet-contracts`' `gas.rs`: a [`Schedule`] of per-operation
+//! weights and a [`Token`] trait that turns a single operation into a weight
+//! given that schedule. [`GasMeter::charge`] subtracts a token's weight from
+//! the remaining budget, returning `Error::OutOfGas` rather than going
+//! negative.
+use crate::contract::GasMeter;
+use ceres_executor::{Error, Result};
+
+/// Per-operation weights charged by the metering host functions.
+///
+/// Per-byte fields are multiplied by the size of the buffer the operation
+/// touches; base fields are flat costs independent of buffer size.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub storage_read_base: u64,
+    pub storage_read_per_byte: u64,
+    pub storage_write_base: u64,
+    pub storage_write_per_byte: u64,
+    pub event_base: u64,
+    pub event_per_byte: u64,
+    pub event_per_topic: u64,
+    pub instantiate_base: u64,
+    pub call_base: u64,
+    pub hash_per_byte: u64,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            storage_read_base: 1_000,
+            storage_read_per_byte: 10,
+            storage_write_base: 1_000,
+            storage_write_per_byte: 100,
+            event_base: 500,
+            event_per_byte: 10,
+            event_per_topic: 200,
+            instantiate_base: 10_000,
+            call_base: 5_000,
+            hash_per_byte: 5,
+        }
+    }
+}
+
+/// An operation whose cost can be computed from a [`Schedule`].
+pub trait Token {
+    /// The amount of gas this operation costs under `schedule`.
+    fn weight(&self, schedule: &Schedule) -> u64;
+}
+
+/// Reading a value of the wrapped length (in bytes) from contract storage.
+pub struct ReadStorageToken(pub u64);
+
+impl Token for ReadStorageToken {
+    fn weight(&self, schedule: &Schedule) -> u64 {
+        schedule.storage_read_base + schedule.storage_read_per_byte * self.0
+    }
+}
+
+/// Writing a value of the wrapped length (in bytes) to contract storage.
+pub struct WriteStorageToken(pub u64);
+
+impl Token for WriteStorageToken {
+    fn weight(&self, schedule: &Schedule) -> u64 {
+        schedule.storage_write_base + schedule.storage_write_per_byte * self.0
+    }
+}
+
+/// Depositing an event carrying `topics` topics and `data_len` bytes of data.
+pub struct DepositEventToken {
+    pub topics: u64,
+    pub data_len: u64,
+}
+
+impl Token for DepositEventToken {
+    fn weight(&self, schedule: &Schedule) -> u64 {
+        schedule.event_base
+            + schedule.event_per_byte * self.data_len
+            + schedule.event_per_topic * self.topics
+    }
+}
+
+/// Instantiating a child contract.
+pub struct InstantiateToken;
+
+impl Token for InstantiateToken {
+    fn weight(&self, schedule: &Schedule) -> u64 {
+        schedule.instantiate_base
+    }
+}
+
+/// Calling another contract.
+pub struct CallToken;
+
+impl Token for CallToken {
+    fn weight(&self, schedule: &Schedule) -> u64 {
+        schedule.call_base
+    }
+}
+
+/// Hashing the wrapped number of bytes, e.g. for `seal_hash_blake2_256`.
+pub struct HashToken(pub u64);
+
+impl Token for HashToken {
+    fn weight(&self, schedule: &Schedule) -> u64 {
+        schedule.hash_per_byte * self.0
+    }
+}
+
+impl GasMeter {
+    /// Charge `token`'s weight against the remaining gas.
+    ///
+    /// Leaves `gas_left` untouched and returns `Err(Error::OutOfGas)` when
+    /// charging would drive it negative.
+    pub fn charge(&mut self, token: impl Token) -> Result<()> {
+        let amount = token.weight(&self.schedule);
+        self.gas_left = self.gas_left.checked_sub(amount).ok_or(Error::OutOfGas)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+//! `seal_hash_blake2_256`
+//!
+//! The host function that charges `HashToken`.
+use crate::{contract::GasMeter, gas::HashToken, instantiate::blake2_256, Sandbox};
+use ceres_executor::Result;
+use ceres_std::Vec;
+
+impl Sandbox {
+    /// `seal_hash_blake2_256`: hash `input`, charging `HashToken` for its
+    /// length.
+    pub fn seal_hash_blake2_256(
+        &mut self,
+        gas_meter: &mut GasMeter,
+        input: Vec<u8>,
+    ) -> Result<[u8; 32]> {
+        gas_meter.charge(HashToken(input.len() as u64))?;
+
+        Ok(blake2_256(&input))
+    }
+}
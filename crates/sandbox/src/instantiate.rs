@@ -1,10 +1,11 @@
 //! Instantiate Entry
 use crate::{
+    call::CallStack,
     contract::GasMeter,
     flag::{ExecReturnValue, ReturnFlags},
     Sandbox,
 };
-use ceres_executor::Result;
+use ceres_executor::{Error, Result, ReturnCode};
 use ceres_std::Vec;
 
 /// Instantiate Entry
@@ -16,36 +17,121 @@ pub struct InstantiateEntry {
     pub salt: Vec<u8>,
 }
 
+/// Resolves a contract's code and runs its constructor.
+///
+/// `Sandbox` has no notion of how contract code is stored or how a child
+/// `Instance` gets wired up with host functions - that orchestration lives in
+/// `ceres_runtime`, the crate that actually owns the `Cache` and the shared
+/// `Storage`. A `Sandbox` is handed an implementation of this trait at
+/// construction time so `seal_instantiate` can recurse back out into that
+/// orchestration without `ceres_sandbox` depending on `ceres_runtime`.
+pub trait ContractLoader {
+    /// Instantiate the contract stored under `code_hash`, running its
+    /// constructor against `data` and returning the derived account id
+    /// together with the constructor's `ExecReturnValue` and the gas it
+    /// consumed.
+    ///
+    /// Returns `Err(Error::ExecuteFailed(ReturnCode::CodeNotFound))` when no
+    /// contract is known under `code_hash`.
+    ///
+    /// `call_stack` is the reentrancy guard shared with the calling
+    /// `Sandbox`; implementations must hand it on unchanged to whatever
+    /// `Sandbox` ends up running the constructor, so the guard still sees
+    /// every address on the chain once execution recurses out of this
+    /// `Sandbox` and back in.
+    ///
+    /// `persist` controls whether the instantiated contract's state is kept:
+    /// a real instantiate passes `true` so the new instance survives; a
+    /// dry run (`bare_instantiate`) passes `false` so the constructor's
+    /// effects are discarded once it returns, instead of being flushed and
+    /// registered as a real account.
+    fn instantiate(
+        &self,
+        code_hash: [u8; 32],
+        endowment: u64,
+        gas_left: u64,
+        data: &[u8],
+        salt: &[u8],
+        call_stack: CallStack,
+        persist: bool,
+    ) -> Result<([u8; 32], ExecReturnValue, u64)>;
+
+    /// Call the already-instantiated contract living at `address`, running
+    /// its `call` entrypoint against `input` and returning its
+    /// `ExecReturnValue` together with the gas it consumed.
+    ///
+    /// Returns `Err(Error::ExecuteFailed(ReturnCode::NotCallable))` when
+    /// `address` is not a known contract account. See `instantiate` above
+    /// for why `call_stack` must be threaded through unchanged.
+    fn call(
+        &self,
+        address: [u8; 32],
+        gas_left: u64,
+        input: Vec<u8>,
+        call_stack: CallStack,
+    ) -> Result<(ExecReturnValue, u64)>;
+}
+
+/// Derive the address of a to-be-instantiated contract, following the same
+/// scheme as `pallet-contracts`: `blake2_256(code_hash ++ data ++ salt)`.
+///
+/// Distinct `salt`s therefore yield distinct contract addresses even when the
+/// same code is instantiated with identical constructor `data`.
+pub fn derive_address(code_hash: &[u8; 32], data: &[u8], salt: &[u8]) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(32 + data.len() + salt.len());
+    preimage.extend_from_slice(code_hash);
+    preimage.extend_from_slice(data);
+    preimage.extend_from_slice(salt);
+
+    blake2_256(&preimage)
+}
+
+pub(crate) fn blake2_256(data: &[u8]) -> [u8; 32] {
+    use blake2::{digest::consts::U32, Blake2b, Digest};
+
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(data);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hasher.finalize());
+    out
+}
+
 impl Sandbox {
     pub fn instantiate(
         &mut self,
         code_hash: [u8; 32],
-        // endowment: u64,
         gas_meter: &mut GasMeter,
         data: Vec<u8>,
         salt: &[u8],
     ) -> Result<([u8; 32], ExecReturnValue, u32)> {
+        gas_meter.charge(crate::gas::InstantiateToken)?;
+
+        let endowment = 3; // endowment
+
         self.ext.instantiates.push(InstantiateEntry {
             code_hash,
-            endowment: 3, // endowment
-            data: data.to_vec(),
+            endowment,
+            data: data.clone(),
             gas_left: gas_meter.gas_left,
             salt: salt.to_vec(),
         });
 
-        // Get contract from code_hash
-        //
-        // entrypoint
+        let (account_id, ret, gas_consumed) = self.loader.instantiate(
+            code_hash,
+            endowment,
+            gas_meter.gas_left,
+            &data,
+            salt,
+            self.call_stack.clone(),
+            true,
+        )?;
+
+        gas_meter.gas_left = gas_meter.gas_left.saturating_sub(gas_consumed);
 
-        // Call deploy by provided `data`
+        if ret.flags.contains(ReturnFlags::REVERT) {
+            return Err(Error::ExecuteFailed(ReturnCode::CalleeReverted));
+        }
 
-        Ok((
-            code_hash,
-            ExecReturnValue {
-                flags: ReturnFlags::empty(),
-                data: Default::default(),
-            },
-            0,
-        ))
+        Ok((account_id, ret, gas_consumed as u32))
     }
 }
@@ -0,0 +1,43 @@
+//! `seal_get_storage`/`seal_set_storage`
+//!
+//! The host functions that charge `ReadStorageToken`/`WriteStorageToken`.
+use crate::{
+    contract::GasMeter,
+    gas::{ReadStorageToken, WriteStorageToken},
+    Sandbox,
+};
+use ceres_executor::{Error, Result, ReturnCode};
+use ceres_std::Vec;
+
+impl Sandbox {
+    /// `seal_get_storage`: read the value stored under `key`, charging
+    /// `ReadStorageToken` for its length.
+    ///
+    /// Returns `Err(Error::ExecuteFailed(ReturnCode::KeyNotFound))` when no
+    /// value is stored under `key`.
+    pub fn seal_get_storage(&mut self, gas_meter: &mut GasMeter, key: Vec<u8>) -> Result<Vec<u8>> {
+        let value = self
+            .state
+            .get(&key)
+            .ok_or(Error::ExecuteFailed(ReturnCode::KeyNotFound))?;
+
+        gas_meter.charge(ReadStorageToken(value.len() as u64))?;
+
+        Ok(value)
+    }
+
+    /// `seal_set_storage`: write `value` under `key`, charging
+    /// `WriteStorageToken` for its length.
+    pub fn seal_set_storage(
+        &mut self,
+        gas_meter: &mut GasMeter,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<()> {
+        gas_meter.charge(WriteStorageToken(value.len() as u64))?;
+
+        self.state.set(key, value);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use ceres_ri::Instance;
 use ceres_runtime::{MemoryStorage, Runtime};
+use ceres_support::types::Cache;
 use parity_scale_codec::Encode;
 use std::{cell::RefCell, rc::Rc};
 
@@ -8,12 +9,13 @@ fn t(f: fn(rt: &mut Runtime)) {
     let mut args = Runtime::from_contract_and_storage(
         include_bytes!("../contracts/args.contract"),
         shared.clone(),
+        Cache::default(),
         Some(Instance),
     )
     .unwrap();
 
     // deploy
-    assert!(args.deploy("default", vec![], None).is_ok());
+    assert!(args.deploy("default", vec![], None, None).is_ok());
 
     // run test
     f(&mut args);
@@ -23,7 +25,7 @@ fn t(f: fn(rt: &mut Runtime)) {
 fn test_boolean() {
     t(|args: &mut Runtime| {
         assert_eq!(
-            args.call("test_boolean", vec![true.encode()], None)
+            args.call("test_boolean", vec![true.encode()], None, None,)
                 .unwrap(),
             vec![1]
         );
@@ -34,7 +36,8 @@ fn test_boolean() {
 fn test_number() {
     t(|args: &mut Runtime| {
         assert_eq!(
-            args.call("test_number", vec![0.encode()], None).unwrap(),
+            args.call("test_number", vec![0.encode()], None, None,)
+                .unwrap(),
             vec![0, 0, 0, 0]
         );
     })
@@ -45,7 +48,8 @@ fn test_hash() {
     t(|args: &mut Runtime| {
         let hash = [0; 32];
         assert_eq!(
-            args.call("test_hash", vec![hash.to_vec()], None).unwrap(),
+            args.call("test_hash", vec![hash.to_vec()], None, None,)
+                .unwrap(),
             vec![0; 32]
         );
     })
@@ -58,7 +62,8 @@ fn test_boolean_and_number() {
             args.call(
                 "test_boolean_and_number",
                 vec![true.encode(), 1.encode()],
-                None
+                None,
+                None,
             )
             .unwrap(),
             vec![1, 1, 0, 0, 0]
@@ -76,7 +81,8 @@ fn test_boolean_and_hash() {
             args.call(
                 "test_boolean_and_hash",
                 vec![true.encode(), hash.to_vec()],
-                None
+                None,
+                None,
             )
             .unwrap(),
             res
@@ -88,8 +94,13 @@ fn test_boolean_and_hash() {
 fn test_number_and_number() {
     t(|args: &mut Runtime| {
         assert_eq!(
-            args.call("test_number_and_number", vec![0.encode(), 1.encode()], None)
-                .unwrap(),
+            args.call(
+                "test_number_and_number",
+                vec![0.encode(), 1.encode()],
+                None,
+                None,
+            )
+            .unwrap(),
             vec![0, 0, 0, 0, 1, 0, 0, 0]
         );
     })
@@ -105,7 +116,8 @@ fn test_number_and_hash() {
             args.call(
                 "test_number_and_hash",
                 vec![0.encode(), hash.to_vec()],
-                None
+                None,
+                None,
             )
             .unwrap(),
             res,
@@ -124,10 +136,48 @@ fn test_all() {
             args.call(
                 "test_all",
                 vec![0.encode(), hash.to_vec(), true.encode()],
-                None
+                None,
+                None,
             )
             .unwrap(),
             res,
         );
     })
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_out_of_gas() {
+    // The constructor writes the contract's initial state to storage, which
+    // charges a `WriteStorageToken` (at least `storage_write_base`, 1_000)
+    // - far more than a `gas_limit` of 1 - so exhaust gas there.
+    let shared = Rc::new(RefCell::new(MemoryStorage::new()));
+    let mut args = Runtime::from_contract_and_storage(
+        include_bytes!("../contracts/args.contract"),
+        shared,
+        Cache::default(),
+        Some(Instance),
+    )
+    .unwrap();
+
+    match args.deploy("default", vec![], None, Some(1)) {
+        Err(ceres_runtime::Error::DeployContractFailed {
+            error: ceres_executor::Error::OutOfGas,
+        }) => {}
+        other => panic!(
+            "expected a gas-exhausted deploy to fail with OutOfGas, got {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn test_bare_call() {
+    t(|args: &mut Runtime| {
+        let result = args
+            .bare_call("test_boolean", vec![true.encode()], None, 10_000_000_000)
+            .unwrap();
+        assert!(result.flags.is_empty());
+        assert_eq!(result.data, vec![1]);
+        assert!(result.gas_consumed > 0);
+    })
+}
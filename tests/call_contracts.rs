@@ -14,11 +14,23 @@
 use ceres_ri::Instance;
 use ceres_runtime::Runtime;
 use ceres_support::types::Cache;
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, Encode};
+
+fn get(delegator: &mut Runtime) -> i32 {
+    let raw = delegator.call("get", vec![], None, None).unwrap();
+    i32::decode(&mut raw.as_slice()).unwrap()
+}
 
 #[test]
 fn test_call_contracts() {
     env_logger::init();
+    // Shared by every `Runtime::from_contract` call below, so delegator's
+    // own `RuntimeLoader` can resolve accumulator/adder/subber's code when
+    // it deploys them as children - a `Cache::default()` per call would
+    // leave each one's bytecode registered only in a cache that's dropped
+    // right after, and delegator's `deploy("new", ...)` would fail with
+    // `CodeNotFound`.
+    let cache = Cache::default();
     let hashes = [
         include_bytes!("../contracts/accumulator.contract").to_vec(),
         include_bytes!("../contracts/adder.contract").to_vec(),
@@ -26,7 +38,7 @@ fn test_call_contracts() {
     ]
     .iter()
     .map(|contract| {
-        let rt = Runtime::from_contract(contract, Cache::default(), Some(Instance)).unwrap();
+        let rt = Runtime::from_contract(contract, cache.clone(), Some(Instance)).unwrap();
         rt.metadata.source.hash
     })
     .collect::<Vec<String>>();
@@ -34,12 +46,12 @@ fn test_call_contracts() {
     // init delegator
     let mut delegator = Runtime::from_contract(
         include_bytes!("../contracts/delegator.contract"),
-        Cache::default(),
+        cache,
         Some(Instance),
     )
     .unwrap();
 
-    // deploy delegator
+    // deploy delegator, starting with `version: 0` (Adder)
     assert!(delegator
         .deploy(
             "new",
@@ -51,7 +63,23 @@ fn test_call_contracts() {
                 hex::decode(&hashes[2][2..]).unwrap(),
             ],
             None,
+            None,
         )
-        .is_err());
-    // delegator.call("get", vec![], None).unwrap();
+        .is_ok());
+
+    // get: reads back the constructor's initial value
+    assert_eq!(get(&mut delegator), 42);
+
+    // change: delegates to the Adder, so `get` goes up
+    delegator
+        .call("change", vec![1.encode()], None, None)
+        .unwrap();
+    assert_eq!(get(&mut delegator), 43);
+
+    // switch: flips which callee `change` delegates to (Adder -> Subber)
+    delegator.call("switch", vec![], None, None).unwrap();
+    delegator
+        .call("change", vec![1.encode()], None, None)
+        .unwrap();
+    assert_eq!(get(&mut delegator), 42);
 }
@@ -0,0 +1,42 @@
+//! ## CallFlags semantics
+//!
+//! - reentering the caller's own address without `ALLOW_REENTRY` traps with
+//!   `Error::ReentrancyDenied`
+//! - the same reentrant call succeeds once `ALLOW_REENTRY` is set
+//!
+//! `reentrant.contract`'s `reenter` message calls back into its own address
+//! without any `CallFlags` set; `reenter_allowed` does the same but with
+//! `ALLOW_REENTRY` set.
+use ceres_ri::Instance;
+use ceres_runtime::Runtime;
+use ceres_support::types::Cache;
+
+fn deploy() -> Runtime {
+    let mut rt = Runtime::from_contract(
+        include_bytes!("../contracts/reentrant.contract"),
+        Cache::default(),
+        Some(Instance),
+    )
+    .unwrap();
+    rt.deploy("new", vec![], None, None).unwrap();
+    rt
+}
+
+#[test]
+fn test_reenter_denied_without_allow_reentry() {
+    let mut rt = deploy();
+    // `seal_call`'s `Error::ReentrancyDenied` unwinds through the wasm trap
+    // mechanism, so it surfaces here as `CallContractFailed` rather than the
+    // bare `ReentrancyDenied` variant - assert on the trap rather than the
+    // exact wrapping.
+    assert!(
+        rt.call("reenter", vec![], None, None).is_err(),
+        "expected reentering without ALLOW_REENTRY to trap"
+    );
+}
+
+#[test]
+fn test_reenter_allowed_with_allow_reentry() {
+    let mut rt = deploy();
+    assert!(rt.call("reenter_allowed", vec![], None, None).is_ok());
+}
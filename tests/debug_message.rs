@@ -0,0 +1,38 @@
+//! ## `seal_debug_message`
+//!
+//! - with debug recording enabled, `ink::env::debug_println!` output is
+//!   captured and surfaced via `Runtime::take_debug_message`
+//! - with debug recording disabled (the default), the message is dropped and
+//!   `seal_debug_message` reports `ReturnCode::LoggingDisabled` rather than
+//!   failing the call
+//!
+//! `debug.contract`'s `log` message calls `ink::env::debug_println!` once.
+use ceres_ri::Instance;
+use ceres_runtime::Runtime;
+use ceres_support::types::Cache;
+
+fn deploy() -> Runtime {
+    let mut rt = Runtime::from_contract(
+        include_bytes!("../contracts/debug.contract"),
+        Cache::default(),
+        Some(Instance),
+    )
+    .unwrap();
+    rt.deploy("new", vec![], None, None).unwrap();
+    rt
+}
+
+#[test]
+fn test_debug_message_recorded_when_enabled() {
+    let mut rt = deploy();
+    rt.enable_debug_message(true);
+    rt.call("log", vec![], None, None).unwrap();
+    assert!(rt.take_debug_message().is_some());
+}
+
+#[test]
+fn test_debug_message_dropped_when_disabled() {
+    let mut rt = deploy();
+    rt.call("log", vec![], None, None).unwrap();
+    assert!(rt.take_debug_message().is_none());
+}
@@ -0,0 +1,59 @@
+//! ## `seal_deposit_event` topic validation
+//!
+//! - more than `MAX_TOPICS` (4) topics is rejected with `Error::TooManyTopics`
+//! - a repeated topic is rejected with `Error::DuplicateTopics`
+//! - a topic that isn't exactly 32 bytes is rejected with
+//!   `Error::TopicValueTooLarge`
+//! - a well-formed event is recorded in `Runtime::events()`
+//!
+//! `events.contract`'s messages each deposit one event: `emit_ok` with two
+//! distinct 32-byte topics, `emit_too_many_topics` with five,
+//! `emit_duplicate_topics` with the same 32-byte topic twice, and
+//! `emit_bad_topic_length` with a topic that isn't 32 bytes.
+use ceres_ri::Instance;
+use ceres_runtime::Runtime;
+use ceres_support::types::Cache;
+
+fn deploy() -> Runtime {
+    let mut rt = Runtime::from_contract(
+        include_bytes!("../contracts/events.contract"),
+        Cache::default(),
+        Some(Instance),
+    )
+    .unwrap();
+    rt.deploy("new", vec![], None, None).unwrap();
+    rt
+}
+
+#[test]
+fn test_emit_ok_records_event() {
+    let mut rt = deploy();
+    rt.call("emit_ok", vec![], None, None).unwrap();
+    assert_eq!(rt.events().len(), 1);
+    assert_eq!(rt.events()[0].topics.len(), 2);
+}
+
+#[test]
+fn test_too_many_topics_rejected() {
+    let mut rt = deploy();
+    assert!(rt.call("emit_too_many_topics", vec![], None, None).is_err());
+    assert!(rt.events().is_empty());
+}
+
+#[test]
+fn test_duplicate_topics_rejected() {
+    let mut rt = deploy();
+    assert!(rt
+        .call("emit_duplicate_topics", vec![], None, None)
+        .is_err());
+    assert!(rt.events().is_empty());
+}
+
+#[test]
+fn test_topic_wrong_length_rejected() {
+    let mut rt = deploy();
+    assert!(rt
+        .call("emit_bad_topic_length", vec![], None, None)
+        .is_err());
+    assert!(rt.events().is_empty());
+}